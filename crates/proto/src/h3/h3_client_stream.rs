@@ -0,0 +1,440 @@
+// Copyright 2015-2023 Benjamin Fry <benjaminfry@me.com>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::{
+    fmt::{self, Display},
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::{Buf, Bytes};
+use futures_util::future::FutureExt;
+use futures_util::stream::Stream;
+use h3::client::SendRequest;
+use h3_quinn::OpenStreams;
+use http::header::CONTENT_LENGTH;
+use quinn::{ClientConfig, Endpoint, TransportConfig, VarInt};
+use rustls::{version::TLS13, ClientConfig as TlsClientConfig};
+
+use crate::error::ProtoError;
+use crate::http::Version;
+use crate::quic::quic_client_stream::QuinnAsyncUdpSocketAdapter;
+use crate::quic::quic_config;
+use crate::udp::{DnsUdpSocket, QuicLocalAddr, UdpSocket};
+use crate::xfer::{DnsRequest, DnsRequestSender, DnsResponse, DnsResponseStream};
+
+use super::ALPN_H3;
+
+/// The default HTTP/3 path at which a resolver accepts DNS queries.
+const DEFAULT_DNS_QUERY_PATH: &str = "/dns-query";
+
+/// A DNS client connection for DNS-over-HTTP/3
+#[must_use = "futures do nothing unless polled"]
+#[derive(Clone)]
+pub struct H3ClientStream {
+    // Corresponds to the dns-name of the HTTP/3 server
+    name_server_name: Arc<str>,
+    name_server: SocketAddr,
+    path: Arc<str>,
+    send_request: SendRequest<OpenStreams, Bytes>,
+    is_shutdown: bool,
+}
+
+impl Display for H3ClientStream {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            formatter,
+            "H3({},{})",
+            self.name_server, self.name_server_name
+        )
+    }
+}
+
+impl H3ClientStream {
+    /// Builder for H3ClientStream
+    pub fn builder() -> H3ClientStreamBuilder {
+        H3ClientStreamBuilder::default()
+    }
+
+    async fn inner_send(
+        mut send_request: SendRequest<OpenStreams, Bytes>,
+        message: Bytes,
+        name_server_name: Arc<str>,
+        path: Arc<str>,
+    ) -> Result<DnsResponse, ProtoError> {
+        // build up the http request
+        let request =
+            crate::http::request::new(Version::Http3, &name_server_name, &path, message.remaining());
+
+        let request =
+            request.map_err(|err| ProtoError::from(format!("bad http request: {err}")))?;
+
+        tracing::debug!("request: {:#?}", request);
+
+        // Send the request
+        let mut stream = send_request
+            .send_request(request)
+            .await
+            .map_err(|err| ProtoError::from(format!("h3 send_request error: {err}")))?;
+
+        stream
+            .send_data(message)
+            .await
+            .map_err(|e| ProtoError::from(format!("h3 send_data error: {e}")))?;
+
+        stream
+            .finish()
+            .await
+            .map_err(|err| ProtoError::from(format!("received a stream error: {err}")))?;
+
+        let response = stream
+            .recv_response()
+            .await
+            .map_err(|err| ProtoError::from(format!("h3 recv_response error: {err}")))?;
+
+        tracing::debug!("got response: {:#?}", response);
+
+        // get the length of packet
+        let content_length = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+
+        // TODO: what is a good max here?
+        // clamp(512, 4096) says make sure it is at least 512 bytes, and min 4096 says it is at most 4k
+        // just a little protection from malicious actors.
+        let mut response_bytes =
+            bytes::BytesMut::with_capacity(content_length.unwrap_or(512).clamp(512, 4_096));
+
+        while let Some(partial_bytes) = stream
+            .recv_data()
+            .await
+            .map_err(|e| ProtoError::from(format!("h3 recv_data error: {e}")))?
+        {
+            tracing::debug!("got bytes: {}", partial_bytes.remaining());
+            response_bytes.put(partial_bytes);
+        }
+
+        // assert the length
+        if let Some(content_length) = content_length {
+            if response_bytes.len() != content_length {
+                // TODO: make explicit error type
+                return Err(ProtoError::from(format!(
+                    "expected byte length: {content_length}, got: {}",
+                    response_bytes.len()
+                )));
+            }
+        }
+
+        // Was it a successful request?
+        if !response.status().is_success() {
+            let error_string = String::from_utf8_lossy(response_bytes.as_ref());
+
+            // TODO: make explicit error type
+            return Err(ProtoError::from(format!(
+                "http unsuccessful code: {}, message: {}",
+                response.status(),
+                error_string
+            )));
+        }
+
+        // and finally convert the bytes into a DNS message
+        let message = crate::op::Message::from_vec(&response_bytes)?;
+        Ok(DnsResponse::new(message, response_bytes.to_vec()))
+    }
+}
+
+impl DnsRequestSender for H3ClientStream {
+    /// This indicates that the HTTP message was successfully sent, and we now have the response to read.
+    fn send_message(&mut self, mut message: DnsRequest) -> DnsResponseStream {
+        if self.is_shutdown {
+            panic!("can not send messages after stream is shutdown")
+        }
+
+        // per the RFC, a zero ID is used for DoH (and DoH3) just as for DoQ; correlation is by
+        // the HTTP/3 request stream, not the Message ID.
+        message.set_id(0);
+
+        let bytes = match message.to_vec() {
+            Ok(bytes) => bytes,
+            Err(err) => return err.into(),
+        };
+
+        Box::pin(Self::inner_send(
+            self.send_request.clone(),
+            Bytes::from(bytes),
+            Arc::clone(&self.name_server_name),
+            Arc::clone(&self.path),
+        ))
+        .into()
+    }
+
+    fn shutdown(&mut self) {
+        self.is_shutdown = true;
+    }
+
+    fn is_shutdown(&self) -> bool {
+        self.is_shutdown
+    }
+}
+
+impl Stream for H3ClientStream {
+    type Item = Result<(), ProtoError>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.is_shutdown {
+            Poll::Ready(None)
+        } else {
+            Poll::Ready(Some(Ok(())))
+        }
+    }
+}
+
+/// A H3 connection builder for DNS-over-HTTP/3
+#[derive(Clone)]
+pub struct H3ClientStreamBuilder {
+    crypto_config: Option<TlsClientConfig>,
+    transport_config: Arc<TransportConfig>,
+    bind_addr: Option<SocketAddr>,
+}
+
+impl H3ClientStreamBuilder {
+    /// Sets the TLS ClientConfig used for connections from this builder
+    pub fn crypto_config(&mut self, crypto_config: TlsClientConfig) -> &mut Self {
+        self.crypto_config = Some(crypto_config);
+        self
+    }
+
+    /// Sets the address to connect from.
+    pub fn bind_addr(&mut self, bind_addr: SocketAddr) -> &mut Self {
+        self.bind_addr = Some(bind_addr);
+        self
+    }
+
+    /// Creates a new H3Stream to the specified name_server
+    ///
+    /// # Arguments
+    ///
+    /// * `name_server` - IP and Port for the remote DNS resolver
+    /// * `dns_name` - The DNS name, Subject Public Key Info (SPKI) name, as associated to a certificate
+    pub fn build(self, name_server: SocketAddr, dns_name: String) -> H3ClientConnect {
+        self.build_with_path(name_server, dns_name, DEFAULT_DNS_QUERY_PATH.to_string())
+    }
+
+    /// Creates a new H3Stream to the specified name_server answering on `path`
+    pub fn build_with_path(
+        self,
+        name_server: SocketAddr,
+        dns_name: String,
+        path: String,
+    ) -> H3ClientConnect {
+        H3ClientConnect(Box::pin(self.connect(name_server, dns_name, path)) as _)
+    }
+
+    /// Create a H3Stream with existing connection
+    pub fn build_with_future<S, F>(
+        self,
+        future: F,
+        name_server: SocketAddr,
+        dns_name: String,
+        path: String,
+    ) -> H3ClientConnect
+    where
+        S: DnsUdpSocket + QuicLocalAddr + 'static,
+        F: Future<Output = std::io::Result<S>> + Send + 'static,
+    {
+        H3ClientConnect(Box::pin(self.connect_with_future(future, name_server, dns_name, path)) as _)
+    }
+
+    async fn connect_with_future<S, F>(
+        self,
+        future: F,
+        name_server: SocketAddr,
+        dns_name: String,
+        path: String,
+    ) -> Result<H3ClientStream, ProtoError>
+    where
+        S: DnsUdpSocket + QuicLocalAddr + 'static,
+        F: Future<Output = std::io::Result<S>> + Send,
+    {
+        let socket = future.await?;
+        let endpoint_config = quic_config::endpoint();
+        let wrapper = QuinnAsyncUdpSocketAdapter { io: socket };
+        let endpoint = Endpoint::new_with_abstract_socket(
+            endpoint_config,
+            None,
+            wrapper,
+            Arc::new(quinn::TokioRuntime),
+        )?;
+        self.connect_inner(endpoint, name_server, dns_name, path)
+            .await
+    }
+
+    async fn connect(
+        self,
+        name_server: SocketAddr,
+        dns_name: String,
+        path: String,
+    ) -> Result<H3ClientStream, ProtoError> {
+        let connect = if let Some(bind_addr) = self.bind_addr {
+            <tokio::net::UdpSocket as UdpSocket>::connect_with_bind(name_server, bind_addr)
+        } else {
+            <tokio::net::UdpSocket as UdpSocket>::connect(name_server)
+        };
+
+        let socket = connect.await?;
+        let socket = socket.into_std()?;
+        let endpoint_config = quic_config::endpoint();
+        let endpoint = Endpoint::new(endpoint_config, None, socket, Arc::new(quinn::TokioRuntime))?;
+        self.connect_inner(endpoint, name_server, dns_name, path)
+            .await
+    }
+
+    async fn connect_inner(
+        self,
+        mut endpoint: Endpoint,
+        name_server: SocketAddr,
+        dns_name: String,
+        path: String,
+    ) -> Result<H3ClientStream, ProtoError> {
+        // ensure the ALPN protocol is set correctly, the HTTP/3 ALPN differs from DoQ
+        let mut crypto_config = if let Some(crypto_config) = self.crypto_config {
+            crypto_config
+        } else {
+            client_config_tls13()?
+        };
+        if crypto_config.alpn_protocols.is_empty() {
+            crypto_config.alpn_protocols = vec![ALPN_H3.to_vec()];
+        }
+
+        let mut client_config = ClientConfig::new(Arc::new(crypto_config));
+        client_config.transport_config(self.transport_config.clone());
+
+        endpoint.set_default_client_config(client_config);
+
+        let connecting = endpoint.connect(name_server, &dns_name)?;
+        let connection = connecting.await?;
+
+        let h3_connection = h3_quinn::Connection::new(connection);
+        let (mut driver, send_request) = h3::client::new(h3_connection)
+            .await
+            .map_err(|err| ProtoError::from(format!("h3 connection failed: {err}")))?;
+
+        // the driver must be polled for the connection to make progress; it completes when the
+        // connection is closed, so spawn it and drop the handle.
+        tokio::spawn(async move {
+            if let Err(err) = futures_util::future::poll_fn(|cx| driver.poll_close(cx)).await {
+                tracing::warn!("h3 connection driver failed: {err}");
+            }
+        });
+
+        Ok(H3ClientStream {
+            name_server_name: Arc::from(dns_name),
+            name_server,
+            path: Arc::from(path),
+            send_request,
+            is_shutdown: false,
+        })
+    }
+}
+
+/// Default crypto options for HTTP/3
+pub fn client_config_tls13() -> Result<TlsClientConfig, ProtoError> {
+    use rustls::RootCertStore;
+    #[cfg_attr(
+        not(any(feature = "native-certs", feature = "webpki-roots")),
+        allow(unused_mut)
+    )]
+    let mut root_store = RootCertStore::empty();
+    #[cfg(all(feature = "native-certs", not(feature = "webpki-roots")))]
+    {
+        use crate::error::ProtoErrorKind;
+
+        let (added, ignored) =
+            root_store.add_parsable_certificates(&rustls_native_certs::load_native_certs()?);
+
+        if ignored > 0 {
+            tracing::warn!(
+                "failed to parse {} certificate(s) from the native root store",
+                ignored,
+            );
+        }
+
+        if added == 0 {
+            return Err(ProtoErrorKind::NativeCerts.into());
+        }
+    }
+    #[cfg(feature = "webpki-roots")]
+    root_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+            ta.subject,
+            ta.spki,
+            ta.name_constraints,
+        )
+    }));
+
+    Ok(TlsClientConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&TLS13])
+        .expect("TLS 1.3 not supported")
+        .with_root_certificates(root_store)
+        .with_no_client_auth())
+}
+
+impl Default for H3ClientStreamBuilder {
+    fn default() -> Self {
+        let mut transport_config = quic_config::transport();
+        // clients never accept new bidirectional streams
+        transport_config.max_concurrent_bidi_streams(VarInt::from_u32(0));
+
+        Self {
+            crypto_config: None,
+            transport_config: Arc::new(transport_config),
+            bind_addr: None,
+        }
+    }
+}
+
+/// A future that resolves to an H3ClientStream
+pub struct H3ClientConnect(
+    Pin<Box<dyn Future<Output = Result<H3ClientStream, ProtoError>> + Send>>,
+);
+
+impl Future for H3ClientConnect {
+    type Output = Result<H3ClientStream, ProtoError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.poll_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_the_h3_alpn() {
+        // The whole point of this transport is to offer the HTTP/3 ALPN rather than DoQ's `doq`,
+        // so that HTTP-aware middleboxes route it.
+        assert_eq!(ALPN_H3, b"h3");
+
+        let mut config = client_config_tls13().expect("tls13 config");
+        assert!(config.alpn_protocols.is_empty());
+        config.alpn_protocols = vec![ALPN_H3.to_vec()];
+        assert_eq!(config.alpn_protocols, vec![b"h3".to_vec()]);
+    }
+
+    #[test]
+    fn build_defaults_to_the_dns_query_path() {
+        assert_eq!(DEFAULT_DNS_QUERY_PATH, "/dns-query");
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright 2015-2023 Benjamin Fry <benjaminfry@me.com>
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! DNS-over-HTTP/3 client implementation.
+//!
+//! This is the RFC 9250 sibling transport to DNS-over-QUIC: it reuses the same quinn
+//! `Endpoint`/socket plumbing but speaks DNS over HTTP/3 so it traverses HTTP-aware
+//! middleboxes. See [`h3_client_stream`] for the client stream.
+//!
+//! Gated behind the `dns-over-h3` feature, mirroring how `quic` is gated behind
+//! `dns-over-quic`. The crate root declares this module with
+//! `#[cfg(feature = "dns-over-h3")] pub mod h3;`, so the same inner gate is kept here to keep
+//! the module out of builds that do not enable the feature (and pull in `h3`/`h3-quinn`/`http`).
+#![cfg(feature = "dns-over-h3")]
+
+mod h3_client_stream;
+
+pub use self::h3_client_stream::{
+    client_config_tls13, H3ClientConnect, H3ClientStream, H3ClientStreamBuilder,
+};
+
+/// The ALPN protocol identifier negotiated for HTTP/3, as opposed to `doq` for DNS-over-QUIC.
+pub(crate) const ALPN_H3: &[u8] = b"h3";
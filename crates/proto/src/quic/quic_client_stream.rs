@@ -5,23 +5,34 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
 use std::{
     fmt::{self, Display},
     future::Future,
+    io,
     net::SocketAddr,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use futures_util::{future::FutureExt, stream::Stream};
-use quinn::{AsyncUdpSocket, ClientConfig, Connection, Endpoint, TransportConfig, VarInt};
-use rustls::{version::TLS13, ClientConfig as TlsClientConfig};
+use quinn::{
+    AsyncUdpSocket, ClientConfig, Connection, Endpoint, IdleTimeout, TransportConfig, VarInt,
+};
+use rustls::client::{
+    ClientSessionStore as RustlsClientSessionStore, ClientSessionMemoryCache, Resumption,
+    Tls12ClientSessionValue, Tls13ClientSessionValue,
+};
+use rustls::{version::TLS13, ClientConfig as TlsClientConfig, NamedGroup, ServerName};
 
+use crate::rr::{RData, Record, RecordType};
 use crate::udp::{DnsUdpSocket, QuicLocalAddr};
 use crate::{
-    error::ProtoError,
+    error::{ProtoError, ProtoErrorKind},
     quic::quic_stream::{DoqErrorCode, QuicStream},
     udp::UdpSocket,
     xfer::{DnsRequest, DnsRequestSender, DnsResponse, DnsResponseStream},
@@ -35,6 +46,7 @@ pub struct QuicClientStream {
     quic_connection: Connection,
     name_server_name: Arc<str>,
     name_server: SocketAddr,
+    shutdown_error_code: DoqErrorCode,
     is_shutdown: bool,
 }
 
@@ -72,12 +84,159 @@ impl QuicClientStream {
 
         stream.receive().await
     }
+
+    /// Zone transfer variant of [`Self::inner_send`].
+    ///
+    /// RFC 9250 §5.2 allows the server to return more than one response message on the
+    /// stream selected for a query, the primary use case being AXFR/IXFR zone transfers.
+    /// Each message is framed with the same 2-octet length prefix as DNS-over-TCP and the
+    /// server signals the end of the exchange with the STREAM FIN mechanism. This yields one
+    /// [`DnsResponse`] per framed message until the peer closes the stream, leaving correlation
+    /// entirely to the stream (the Message ID stays zero on the wire).
+    ///
+    /// A stream FIN on its own does not prove the transfer is complete: a mid-transfer truncation
+    /// (abrupt close, short read on a length prefix) also surfaces as an end-of-stream. Per
+    /// RFC 5936 §2.2 / RFC 1995, a zone transfer is only complete once the terminating SOA has been
+    /// received, so this tracks the opening SOA's serial and treats the closing stream as success
+    /// only after a matching terminating SOA; otherwise the stream ends with an error rather than
+    /// silently yielding an incomplete zone.
+    fn inner_send_zone_transfer(
+        connection: Connection,
+        message: DnsRequest,
+    ) -> impl Stream<Item = Result<DnsResponse, ProtoError>> {
+        futures_util::stream::try_unfold(
+            ZoneTransferState::Query(connection, message),
+            |state| async move {
+                let mut transfer = match state {
+                    ZoneTransferState::Query(connection, message) => {
+                        let (send_stream, recv_stream) = connection.open_bi().await?;
+                        let mut stream = QuicStream::new(send_stream, recv_stream);
+                        stream.send(message.into_parts().0).await?;
+                        stream.finish().await?;
+                        ZoneTransfer::new(stream)
+                    }
+                    ZoneTransferState::Receive(transfer) => transfer,
+                };
+
+                // Read length-prefixed messages until the peer signals FIN (read returns EOF).
+                // On FIN or on a decode error we stop threading the stream through the unfold
+                // state; dropping it tears the QUIC stream down cleanly so dangling streams
+                // don't accumulate on either end.
+                match transfer.stream.receive().await {
+                    Ok(response) => {
+                        transfer.observe(&response);
+                        Ok(Some((response, ZoneTransferState::Receive(transfer))))
+                    }
+                    // A clean end-of-stream only completes the transfer once the terminating SOA
+                    // has been seen; an EOF before it is a truncated zone, reported as an error.
+                    Err(err) if is_stream_finished(&err) => {
+                        if transfer.complete {
+                            Ok(None)
+                        } else {
+                            Err(ProtoError::from(
+                                "DoQ zone transfer stream closed before the terminating SOA record",
+                            ))
+                        }
+                    }
+                    Err(err) => Err(err),
+                }
+            },
+        )
+    }
+}
+
+/// Drives the multi-message zone transfer stream produced by
+/// [`QuicClientStream::inner_send_zone_transfer`].
+enum ZoneTransferState {
+    /// The query has not been sent yet.
+    Query(Connection, DnsRequest),
+    /// The query has been sent; keep reading responses off the stream.
+    Receive(ZoneTransfer),
+}
+
+/// Tracks zone-transfer completion so a stream FIN can be distinguished from a truncated transfer.
+struct ZoneTransfer {
+    stream: QuicStream,
+    /// Serial of the opening SOA, captured from the first response.
+    opening_serial: Option<u32>,
+    /// Total answer records seen so far, used to tell a lone opening SOA from a closing SOA.
+    records_seen: usize,
+    /// Set once a terminating SOA matching [`Self::opening_serial`] has been received.
+    complete: bool,
+}
+
+impl ZoneTransfer {
+    fn new(stream: QuicStream) -> Self {
+        Self {
+            stream,
+            opening_serial: None,
+            records_seen: 0,
+            complete: false,
+        }
+    }
+
+    /// Folds a freshly received response into the completion state.
+    ///
+    /// An AXFR/IXFR opens and closes with an SOA carrying the same serial; the transfer is complete
+    /// once a message ends with that terminating SOA (and it is not merely the opening record).
+    fn observe(&mut self, response: &DnsResponse) {
+        let answers = response.answers();
+
+        if self.opening_serial.is_none() {
+            self.opening_serial = answers.first().and_then(soa_serial);
+        }
+        self.records_seen += answers.len();
+
+        if let (Some(opening), Some(closing)) =
+            (self.opening_serial, answers.last().and_then(soa_serial))
+        {
+            if opening == closing && self.records_seen > 1 {
+                self.complete = true;
+            }
+        }
+    }
+}
+
+/// Returns the SOA serial carried by `record`, or `None` if it is not an SOA record.
+fn soa_serial(record: &Record) -> Option<u32> {
+    match record.data() {
+        Some(RData::SOA(soa)) => Some(soa.serial()),
+        _ => None,
+    }
+}
+
+/// Returns true when `err` signals that the peer has closed the stream with FIN, i.e. the
+/// read reached the end of the stream at a message boundary with no further data to decode.
+///
+/// A clean FIN surfaces as an unexpected-EOF raised while reading the next length prefix. Read
+/// wrappers differ in how they surface that: it may arrive as [`ProtoErrorKind::Io`] directly, or
+/// as an [`io::Error`] further down the error source chain. Both are treated as end-of-stream so correlation stays purely by
+/// stream, rather than matching a formatted error string that could change out from under us.
+/// Completion is then validated separately by the terminating SOA (see
+/// [`QuicClientStream::inner_send_zone_transfer`]).
+fn is_stream_finished(err: &ProtoError) -> bool {
+    if let ProtoErrorKind::Io(io) = err.kind() {
+        if io.kind() == io::ErrorKind::UnexpectedEof {
+            return true;
+        }
+    }
+
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<io::Error>() {
+            return io.kind() == io::ErrorKind::UnexpectedEof;
+        }
+        source = err.source();
+    }
+
+    false
 }
 
 impl DnsRequestSender for QuicClientStream {
     /// The send loop for QUIC in DNS stipulates that a new QUIC "stream" should be opened and use for sending data.
     ///
-    /// It should be closed after receiving the response. TODO: AXFR/IXFR support...
+    /// It should be closed after receiving the response. AXFR/IXFR zone transfers may span
+    /// multiple response messages on the stream (see [`QuicClientStream::inner_send_zone_transfer`]).
     ///
     /// ```text
     /// 5.2. Stream Mapping and Usage
@@ -119,13 +278,28 @@ impl DnsRequestSender for QuicClientStream {
             panic!("can not send messages after stream is shutdown")
         }
 
-        Box::pin(Self::inner_send(self.quic_connection.clone(), message)).into()
+        // Zone transfers (AXFR/IXFR) may be answered with more than one response message on
+        // the stream, so those take the multi-message path; everything else expects exactly one.
+        let is_zone_transfer = message
+            .queries()
+            .iter()
+            .any(|query| matches!(query.query_type(), RecordType::AXFR | RecordType::IXFR));
+
+        if is_zone_transfer {
+            Box::pin(Self::inner_send_zone_transfer(
+                self.quic_connection.clone(),
+                message,
+            ))
+            .into()
+        } else {
+            Box::pin(Self::inner_send(self.quic_connection.clone(), message)).into()
+        }
     }
 
     fn shutdown(&mut self) {
         self.is_shutdown = true;
         self.quic_connection
-            .close(DoqErrorCode::NoError.into(), b"Shutdown");
+            .close(self.shutdown_error_code.into(), b"Shutdown");
     }
 
     fn is_shutdown(&self) -> bool {
@@ -149,8 +323,62 @@ impl Stream for QuicClientStream {
 #[derive(Clone)]
 pub struct QuicClientStreamBuilder {
     crypto_config: Option<TlsClientConfig>,
-    transport_config: Arc<TransportConfig>,
-    bind_addr: Option<SocketAddr>,
+    transport: TransportOverrides,
+    endpoint_source: EndpointSource,
+    session_store: Arc<dyn SessionStore>,
+    shutdown_error_code: DoqErrorCode,
+}
+
+/// Caller overrides applied on top of [`quic_config::transport()`] when the builder is frozen.
+///
+/// `quinn::TransportConfig` is neither `Clone` nor cheap to mutate through a shared `Arc`, so the
+/// knobs are recorded here (each `None` meaning "leave the crate default") and materialised into a
+/// fresh `TransportConfig` at build time. Keeping them as plain values lets the builder stay
+/// `Clone` and lets the setters work on a cloned builder without panicking.
+#[derive(Clone, Default)]
+struct TransportOverrides {
+    max_idle_timeout: Option<Option<IdleTimeout>>,
+    keep_alive_interval: Option<Option<Duration>>,
+    datagram_receive_buffer_size: Option<Option<usize>>,
+    datagram_send_buffer_size: Option<usize>,
+}
+
+impl TransportOverrides {
+    /// Freezes the overrides into the `TransportConfig` shared by every connection this builder
+    /// makes, starting from the crate defaults.
+    fn build(&self) -> Arc<TransportConfig> {
+        let mut transport_config = quic_config::transport();
+        // clients never accept new bidirectional streams
+        transport_config.max_concurrent_bidi_streams(VarInt::from_u32(0));
+
+        if let Some(timeout) = self.max_idle_timeout {
+            transport_config.max_idle_timeout(timeout);
+        }
+        if let Some(interval) = self.keep_alive_interval {
+            transport_config.keep_alive_interval(interval);
+        }
+        if let Some(size) = self.datagram_receive_buffer_size {
+            transport_config.datagram_receive_buffer_size(size);
+        }
+        if let Some(size) = self.datagram_send_buffer_size {
+            transport_config.datagram_send_buffer_size(size);
+        }
+
+        Arc::new(transport_config)
+    }
+}
+
+/// Where a [`QuicClientStreamBuilder`] gets the `quinn::Endpoint` it connects on.
+///
+/// Constructing an `Endpoint` binds a UDP socket and spins up an event loop, so callers opening
+/// many connections can build one and share it across every client stream instead of paying that
+/// cost per connection.
+#[derive(Clone)]
+enum EndpointSource {
+    /// Bind a fresh UDP socket (optionally at `bind_addr`) and build a new endpoint per connection.
+    Bind(Option<SocketAddr>),
+    /// Reuse a caller-provided endpoint, sharing its UDP socket and event loop.
+    Shared(Endpoint),
 }
 
 impl QuicClientStreamBuilder {
@@ -162,7 +390,67 @@ impl QuicClientStreamBuilder {
 
     /// Sets the address to connect from.
     pub fn bind_addr(&mut self, bind_addr: SocketAddr) -> &mut Self {
-        self.bind_addr = Some(bind_addr);
+        self.endpoint_source = EndpointSource::Bind(Some(bind_addr));
+        self
+    }
+
+    /// Uses a pre-built, shared `quinn::Endpoint` for connections from this builder.
+    ///
+    /// All client streams built from this builder then share the endpoint's single UDP socket and
+    /// event loop, rather than each binding a new socket. This replaces an earlier
+    /// [`Self::bind_addr`]; a later call to [`Self::bind_addr`] replaces the shared endpoint.
+    pub fn endpoint(&mut self, endpoint: Endpoint) -> &mut Self {
+        self.endpoint_source = EndpointSource::Shared(endpoint);
+        self
+    }
+
+    /// Installs a session ticket store used for TLS 1.3 0-RTT resumption.
+    ///
+    /// The store is keyed by `name_server_name`, so tickets issued by a resolver can be presented
+    /// on a later connection to that same resolver to skip a round trip. The default is an
+    /// in-memory store ([`InMemorySessionStore`]); pass a disk-backed implementation to persist
+    /// tickets across process runs.
+    pub fn session_store(&mut self, session_store: Arc<dyn SessionStore>) -> &mut Self {
+        self.session_store = session_store;
+        self
+    }
+
+    /// Sets the maximum duration of inactivity before the connection is considered lost.
+    ///
+    /// `None` disables the idle timeout entirely.
+    pub fn max_idle_timeout(&mut self, timeout: Option<IdleTimeout>) -> &mut Self {
+        self.transport.max_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the period of inactivity after which a keep-alive packet is sent.
+    ///
+    /// Set this below [`Self::max_idle_timeout`] on long-lived resolver connections to keep NAT
+    /// bindings alive. `None` disables keep-alives.
+    pub fn keep_alive_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.transport.keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Sets the maximum amount of memory buffered for receiving unreliable datagrams.
+    ///
+    /// `None` disables datagram reception.
+    pub fn datagram_receive_buffer_size(&mut self, size: Option<usize>) -> &mut Self {
+        self.transport.datagram_receive_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the maximum amount of memory buffered for sending unreliable datagrams.
+    pub fn datagram_send_buffer_size(&mut self, size: usize) -> &mut Self {
+        self.transport.datagram_send_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the error code reported to the peer when the stream is shut down.
+    ///
+    /// Defaults to [`DoqErrorCode::NoError`].
+    pub fn shutdown_error_code(&mut self, error_code: DoqErrorCode) -> &mut Self {
+        self.shutdown_error_code = error_code;
         self
     }
 
@@ -200,6 +488,13 @@ impl QuicClientStreamBuilder {
         S: DnsUdpSocket + QuicLocalAddr + 'static,
         F: Future<Output = std::io::Result<S>> + Send,
     {
+        // A shared endpoint already owns its socket, so the provided future is only used when we
+        // need to bind a new one.
+        if let EndpointSource::Shared(endpoint) = &self.endpoint_source {
+            let endpoint = endpoint.clone();
+            return self.connect_inner(endpoint, name_server, dns_name).await;
+        }
+
         let socket = future.await?;
         let endpoint_config = quic_config::endpoint();
         let wrapper = QuinnAsyncUdpSocketAdapter { io: socket };
@@ -217,7 +512,15 @@ impl QuicClientStreamBuilder {
         name_server: SocketAddr,
         dns_name: String,
     ) -> Result<QuicClientStream, ProtoError> {
-        let connect = if let Some(bind_addr) = self.bind_addr {
+        let bind_addr = match &self.endpoint_source {
+            EndpointSource::Shared(endpoint) => {
+                let endpoint = endpoint.clone();
+                return self.connect_inner(endpoint, name_server, dns_name).await;
+            }
+            EndpointSource::Bind(bind_addr) => *bind_addr,
+        };
+
+        let connect = if let Some(bind_addr) = bind_addr {
             <tokio::net::UdpSocket as UdpSocket>::connect_with_bind(name_server, bind_addr)
         } else {
             <tokio::net::UdpSocket as UdpSocket>::connect(name_server)
@@ -245,10 +548,16 @@ impl QuicClientStreamBuilder {
         if crypto_config.alpn_protocols.is_empty() {
             crypto_config.alpn_protocols = vec![quic_stream::DOQ_ALPN.to_vec()];
         }
+
+        // Route TLS 1.3 session tickets through the pluggable store so 0-RTT resumption can reuse
+        // tickets from earlier connections (and, with a disk-backed store, earlier process runs).
+        crypto_config.resumption = Resumption::store(Arc::new(SessionStoreAdapter::new(
+            self.session_store.clone(),
+        )));
         let early_data_enabled = crypto_config.enable_early_data;
 
         let mut client_config = ClientConfig::new(Arc::new(crypto_config));
-        client_config.transport_config(self.transport_config.clone());
+        client_config.transport_config(self.transport.build());
 
         endpoint.set_default_client_config(client_config);
 
@@ -268,6 +577,7 @@ impl QuicClientStreamBuilder {
             quic_connection,
             name_server_name: Arc::from(dns_name),
             name_server,
+            shutdown_error_code: self.shutdown_error_code,
             is_shutdown: false,
         })
     }
@@ -319,18 +629,108 @@ pub fn client_config_tls13() -> Result<TlsClientConfig, ProtoError> {
 
 impl Default for QuicClientStreamBuilder {
     fn default() -> Self {
-        let mut transport_config = quic_config::transport();
-        // clients never accept new bidirectional streams
-        transport_config.max_concurrent_bidi_streams(VarInt::from_u32(0));
-
         Self {
             crypto_config: None,
-            transport_config: Arc::new(transport_config),
-            bind_addr: None,
+            transport: TransportOverrides::default(),
+            endpoint_source: EndpointSource::Bind(None),
+            session_store: Arc::new(InMemorySessionStore::default()),
+            shutdown_error_code: DoqErrorCode::NoError,
         }
     }
 }
 
+/// A pluggable store for TLS 1.3 session tickets, enabling DoQ 0-RTT resumption.
+///
+/// Entries are keyed by the `name_server_name` a stream was built with, so a ticket issued by a
+/// resolver can be presented on a later connection to skip a round trip. [`InMemorySessionStore`]
+/// is the default; a caller can provide a disk-backed implementation to carry tickets across
+/// process runs. See [`QuicClientStreamBuilder::session_store`].
+pub trait SessionStore: Send + Sync + Debug {
+    /// Takes a stored ticket for `name_server_name`, if one is cached. Tickets are single use, so
+    /// the returned ticket is removed from the store.
+    fn get(&self, name_server_name: &str) -> Option<Tls13ClientSessionValue>;
+
+    /// Stores a freshly issued ticket for `name_server_name`.
+    fn put(&self, name_server_name: &str, value: Tls13ClientSessionValue);
+}
+
+/// The default in-memory [`SessionStore`]; tickets live only for the life of the process.
+#[derive(Debug, Default)]
+pub struct InMemorySessionStore {
+    tickets: Mutex<HashMap<String, Vec<Tls13ClientSessionValue>>>,
+}
+
+impl SessionStore for InMemorySessionStore {
+    fn get(&self, name_server_name: &str) -> Option<Tls13ClientSessionValue> {
+        let mut tickets = self.tickets.lock().expect("session store poisoned");
+        tickets.get_mut(name_server_name).and_then(Vec::pop)
+    }
+
+    fn put(&self, name_server_name: &str, value: Tls13ClientSessionValue) {
+        let mut tickets = self.tickets.lock().expect("session store poisoned");
+        tickets.entry(name_server_name.to_owned()).or_default().push(value);
+    }
+}
+
+/// Bridges a crate-level [`SessionStore`] into rustls's [`RustlsClientSessionStore`].
+///
+/// TLS 1.3 tickets — the state that matters for 0-RTT — are delegated to the pluggable store,
+/// keyed by server name. The remaining key-exchange hints and TLS 1.2 sessions stay in a small
+/// in-memory cache, matching rustls's own default.
+#[derive(Debug)]
+struct SessionStoreAdapter {
+    store: Arc<dyn SessionStore>,
+    inner: Arc<dyn RustlsClientSessionStore>,
+}
+
+impl SessionStoreAdapter {
+    fn new(store: Arc<dyn SessionStore>) -> Self {
+        Self {
+            store,
+            inner: Arc::new(ClientSessionMemoryCache::new(256)),
+        }
+    }
+}
+
+/// Renders a `ServerName` into the string key used by [`SessionStore`].
+fn session_store_key(name: &ServerName) -> String {
+    match name {
+        ServerName::DnsName(dns) => dns.as_ref().to_owned(),
+        ServerName::IpAddress(ip) => ip.to_string(),
+        _ => format!("{name:?}"),
+    }
+}
+
+impl RustlsClientSessionStore for SessionStoreAdapter {
+    fn set_kx_hint(&self, server_name: &ServerName, group: NamedGroup) {
+        self.inner.set_kx_hint(server_name, group)
+    }
+
+    fn kx_hint(&self, server_name: &ServerName) -> Option<NamedGroup> {
+        self.inner.kx_hint(server_name)
+    }
+
+    fn set_tls12_session(&self, server_name: &ServerName, value: Tls12ClientSessionValue) {
+        self.inner.set_tls12_session(server_name, value)
+    }
+
+    fn tls12_session(&self, server_name: &ServerName) -> Option<Tls12ClientSessionValue> {
+        self.inner.tls12_session(server_name)
+    }
+
+    fn remove_tls12_session(&self, server_name: &ServerName) {
+        self.inner.remove_tls12_session(server_name)
+    }
+
+    fn insert_tls13_ticket(&self, server_name: &ServerName, value: Tls13ClientSessionValue) {
+        self.store.put(&session_store_key(server_name), value);
+    }
+
+    fn take_tls13_ticket(&self, server_name: &ServerName) -> Option<Tls13ClientSessionValue> {
+        self.store.get(&session_store_key(server_name))
+    }
+}
+
 /// A future that resolves to an QuicClientStream
 pub struct QuicClientConnect(
     Pin<Box<dyn Future<Output = Result<QuicClientStream, ProtoError>> + Send>>,
@@ -358,8 +758,8 @@ impl Future for QuicClientResponse {
 }
 
 /// Wrapper used for quinn::Endpoint::new_with_abstract_socket
-struct QuinnAsyncUdpSocketAdapter<S: DnsUdpSocket + QuicLocalAddr> {
-    io: S,
+pub(crate) struct QuinnAsyncUdpSocketAdapter<S: DnsUdpSocket + QuicLocalAddr> {
+    pub(crate) io: S,
 }
 
 impl<S: DnsUdpSocket + QuicLocalAddr> Debug for QuinnAsyncUdpSocketAdapter<S> {
@@ -368,42 +768,184 @@ impl<S: DnsUdpSocket + QuicLocalAddr> Debug for QuinnAsyncUdpSocketAdapter<S> {
     }
 }
 
-/// TODO: Naive implementation. Look forward to future improvements.
+/// Metadata describing a datagram received through [`UdpSocketCapabilities::poll_recv_meta`].
+///
+/// `stride` is the GRO segment size: quinn splits the receive buffer into `len / stride` segments,
+/// so a socket without GRO reports `stride == len` (a single segment).
+pub struct RecvSegments {
+    /// Total number of bytes written into the receive buffer.
+    pub len: usize,
+    /// Size of each coalesced segment; equal to `len` when GRO is not in use.
+    pub stride: usize,
+    /// Source address of the datagram.
+    pub addr: SocketAddr,
+    /// ECN codepoint read off the datagram, if the socket can report it.
+    pub ecn: Option<quinn::udp::EcnCodepoint>,
+}
+
+/// Optional GSO/GRO/ECN capabilities a [`DnsUdpSocket`] may advertise to the QUIC adapter.
+///
+/// `quinn-udp` probes the platform for Generic Segmentation/Receive Offload and for the ECN
+/// socket options (`IP_TOS`/`IPV6_TCLASS`, alongside `SO_RXQ_OVFL`); a socket that knows its
+/// platform supports these can advertise them here so the adapter coalesces outgoing datagrams
+/// into one GSO write, splits received GRO super-buffers by `stride`, and propagates ECN
+/// codepoints for accurate congestion response. The provided methods describe a socket with none
+/// of these capabilities and fall back to the per-datagram [`DnsUdpSocket`] calls, which preserves
+/// the original behavior.
+///
+/// A blanket impl provides these defaults for every [`DnsUdpSocket`], so the capability is purely
+/// additive: existing sockets keep working through the per-datagram fallback with no source change,
+/// and the adapter queries the methods at runtime to decide whether it can coalesce or split.
+pub trait UdpSocketCapabilities: DnsUdpSocket {
+    /// Maximum number of UDP payload segments the socket can emit in a single GSO send; `1`
+    /// disables send coalescing.
+    fn max_gso_segments(&self) -> usize {
+        1
+    }
+
+    /// Maximum number of segments a received GRO super-buffer may be split into; `1` disables
+    /// receive splitting.
+    fn max_gro_segments(&self) -> usize {
+        1
+    }
+
+    /// Sends `contents` — a run of `stride`-sized segments sharing `destination` and `ecn` — as a
+    /// single GSO datagram when supported.
+    ///
+    /// The default implementation has no GSO, so it splits `contents` back into `stride`-sized
+    /// datagrams and sends them one at a time, returning the number of segments written.
+    fn poll_send_segments(
+        &self,
+        cx: &mut Context<'_>,
+        contents: &[u8],
+        stride: usize,
+        destination: SocketAddr,
+        _ecn: Option<quinn::udp::EcnCodepoint>,
+    ) -> Poll<io::Result<usize>> {
+        let chunk_size = if stride == 0 { contents.len().max(1) } else { stride };
+        let mut sent = 0;
+        for chunk in contents.chunks(chunk_size) {
+            match self.poll_send_to(cx, chunk, destination) {
+                Poll::Ready(Ok(_)) => sent += 1,
+                Poll::Ready(Err(_)) if sent != 0 => return Poll::Ready(Ok(sent)),
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => {
+                    return if sent == 0 {
+                        Poll::Pending
+                    } else {
+                        Poll::Ready(Ok(sent))
+                    }
+                }
+            }
+        }
+        Poll::Ready(Ok(sent))
+    }
+
+    /// Receives a single datagram, reporting the GRO segment size and ECN codepoint when the
+    /// socket can provide them.
+    ///
+    /// The default implementation reads one datagram with no offload information: a single
+    /// segment (`stride == len`) and no ECN.
+    fn poll_recv_meta(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<RecvSegments>> {
+        self.poll_recv_from(cx, buf).map(|res| {
+            res.map(|(len, addr)| RecvSegments {
+                len,
+                stride: len,
+                addr,
+                ecn: None,
+            })
+        })
+    }
+}
+
+impl<S: DnsUdpSocket> UdpSocketCapabilities for S {}
+
+/// Number of consecutive transmits, starting at `transmits[0]`, that can ride a single GSO
+/// datagram: they must share the first transmit's destination and ECN, and every segment but the
+/// last must be exactly `stride` bytes (the kernel derives the trailing segment length from the
+/// total). Returns at least 1, and 1 whenever the socket can't coalesce (`max_gso <= 1`) or the
+/// first transmit is empty.
+fn gso_batch_len(transmits: &[quinn::udp::Transmit], stride: usize, max_gso: usize) -> usize {
+    let mut batch = 1;
+    if max_gso > 1 && stride != 0 {
+        let first = &transmits[0];
+        while batch < transmits.len()
+            && batch < max_gso
+            && transmits[batch - 1].contents.len() == stride
+        {
+            let next = &transmits[batch];
+            if next.destination != first.destination || next.ecn != first.ecn {
+                break;
+            }
+            batch += 1;
+        }
+    }
+    batch
+}
+
 impl<S: DnsUdpSocket + QuicLocalAddr + 'static> AsyncUdpSocket for QuinnAsyncUdpSocketAdapter<S> {
     fn poll_send(
         &self,
         _state: &quinn::udp::UdpState,
         cx: &mut Context<'_>,
         transmits: &[quinn::udp::Transmit],
-    ) -> Poll<std::io::Result<usize>> {
-        // logics from quinn-udp::fallback.rs
+    ) -> Poll<io::Result<usize>> {
         let io = &self.io;
+        let max_gso = io.max_gso_segments();
+
+        // `sent` counts the transmits fully handed off so far; each batch coalesces one or more
+        // consecutive transmits sharing a destination into a single GSO write.
         let mut sent = 0;
-        for transmit in transmits {
-            match io.poll_send_to(cx, &transmit.contents, transmit.destination) {
-                Poll::Ready(ready) => match ready {
-                    Ok(_) => {
-                        sent += 1;
-                    }
-                    // We need to report that some packets were sent in this case, so we rely on
-                    // errors being either harmlessly transient (in the case of WouldBlock) or
-                    // recurring on the next call.
-                    Err(_) if sent != 0 => return Poll::Ready(Ok(sent)),
-                    Err(e) => {
-                        if e.kind() == std::io::ErrorKind::WouldBlock {
-                            return Poll::Ready(Err(e));
-                        }
+        while sent < transmits.len() {
+            let first = &transmits[sent];
+            let stride = first.contents.len();
 
-                        // Other errors are ignored, since they will ususally be handled
-                        // by higher level retransmits and timeouts.
-                        // - PermissionDenied errors have been observed due to iptable rules.
-                        //   Those are not fatal errors, since the
-                        //   configuration can be dynamically changed.
-                        // - Destination unreachable errors have been observed for other
-                        // log_sendmsg_error(&mut self.last_send_error, e, transmit);
-                        sent += 1;
+            let batch = gso_batch_len(&transmits[sent..], stride, max_gso);
+
+            let contents = if batch == 1 {
+                first.contents.to_vec()
+            } else {
+                let mut buf = Vec::with_capacity(stride * batch);
+                for transmit in &transmits[sent..sent + batch] {
+                    buf.extend_from_slice(&transmit.contents);
+                }
+                buf
+            };
+
+            match io.poll_send_segments(cx, &contents, stride, first.destination, first.ecn) {
+                // Each written segment maps to one transmit in the batch, so advance by the count
+                // actually sent. A partial send (the fallback can send some segments then hit
+                // WouldBlock) leaves the rest unsent; report progress and let quinn retry them
+                // rather than counting datagrams that never went out.
+                Poll::Ready(Ok(n)) => {
+                    let n = n.min(batch);
+                    sent += n;
+                    if n < batch {
+                        return Poll::Ready(Ok(sent));
                     }
-                },
+                }
+                // We need to report that some packets were sent in this case, so we rely on
+                // errors being either harmlessly transient (in the case of WouldBlock) or
+                // recurring on the next call.
+                Poll::Ready(Err(_)) if sent != 0 => return Poll::Ready(Ok(sent)),
+                Poll::Ready(Err(e)) => {
+                    if e.kind() == io::ErrorKind::WouldBlock {
+                        return Poll::Ready(Err(e));
+                    }
+
+                    // Other errors are ignored, since they will ususally be handled
+                    // by higher level retransmits and timeouts.
+                    // - PermissionDenied errors have been observed due to iptable rules.
+                    //   Those are not fatal errors, since the
+                    //   configuration can be dynamically changed.
+                    // - Destination unreachable errors have been observed for other
+                    // log_sendmsg_error(&mut self.last_send_error, e, transmit);
+                    sent += batch;
+                }
                 Poll::Pending => {
                     return if sent == 0 {
                         Poll::Pending
@@ -419,26 +961,28 @@ impl<S: DnsUdpSocket + QuicLocalAddr + 'static> AsyncUdpSocket for QuinnAsyncUdp
     fn poll_recv(
         &self,
         cx: &mut Context<'_>,
-        bufs: &mut [std::io::IoSliceMut<'_>],
+        bufs: &mut [io::IoSliceMut<'_>],
         meta: &mut [quinn::udp::RecvMeta],
-    ) -> Poll<std::io::Result<usize>> {
-        // logics from quinn-udp::fallback.rs
-
+    ) -> Poll<io::Result<usize>> {
         let io = &self.io;
         let Some(buf) = bufs.get_mut(0) else {
-            return Poll::Ready(Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "no buf",
-            )));
+            return Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidInput, "no buf")));
         };
-        match io.poll_recv_from(cx, buf.as_mut()) {
+        match io.poll_recv_meta(cx, buf.as_mut()) {
             Poll::Ready(res) => match res {
-                Ok((len, addr)) => {
+                Ok(recv) => {
                     meta[0] = quinn::udp::RecvMeta {
-                        len,
-                        stride: len,
-                        addr,
-                        ecn: None,
+                        len: recv.len,
+                        // Splitting a GRO super-buffer by `stride` lets quinn process every
+                        // coalesced segment; `stride == len` leaves it as a single datagram,
+                        // which is what a socket reporting `max_gro_segments() <= 1` gets.
+                        stride: if io.max_gro_segments() > 1 {
+                            recv.stride
+                        } else {
+                            recv.len
+                        },
+                        addr: recv.addr,
+                        ecn: recv.ecn,
                         dst_ip: None,
                     };
                     Poll::Ready(Ok(1))
@@ -449,7 +993,66 @@ impl<S: DnsUdpSocket + QuicLocalAddr + 'static> AsyncUdpSocket for QuinnAsyncUdp
         }
     }
 
-    fn local_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+    fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
         self.io.local_addr()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eof_read_terminates_zone_transfer() {
+        // A FIN surfaces as an unexpected-EOF on the next read; that must be recognised as the
+        // end of the stream rather than propagate as a read error to the caller.
+        let direct = ProtoError::from(io::Error::from(io::ErrorKind::UnexpectedEof));
+        assert!(is_stream_finished(&direct));
+
+        let named = ProtoError::from(io::Error::new(io::ErrorKind::UnexpectedEof, "early eof"));
+        assert!(is_stream_finished(&named));
+    }
+
+    #[test]
+    fn other_read_errors_do_not_terminate_zone_transfer() {
+        let reset = ProtoError::from(io::Error::from(io::ErrorKind::ConnectionReset));
+        assert!(!is_stream_finished(&reset));
+
+        let decode = ProtoError::from("failed to decode message".to_string());
+        assert!(!is_stream_finished(&decode));
+    }
+
+    fn transmit(dest: SocketAddr, ecn: Option<quinn::udp::EcnCodepoint>, len: usize) -> quinn::udp::Transmit {
+        quinn::udp::Transmit {
+            destination: dest,
+            ecn,
+            contents: vec![0u8; len].into(),
+            segment_size: None,
+            src_ip: None,
+        }
+    }
+
+    #[test]
+    fn gso_coalesces_matching_transmits() {
+        let dest: SocketAddr = "127.0.0.1:853".parse().unwrap();
+        let other: SocketAddr = "127.0.0.2:853".parse().unwrap();
+
+        // A run of equal-sized datagrams to the same destination coalesces up to `max_gso`, with
+        // a shorter trailing segment allowed only as the last member of the batch.
+        let transmits = vec![
+            transmit(dest, None, 1200),
+            transmit(dest, None, 1200),
+            transmit(dest, None, 800),
+            transmit(dest, None, 1200),
+        ];
+        assert_eq!(gso_batch_len(&transmits, 1200, 8), 3);
+
+        // A differing destination ends the batch.
+        let transmits = vec![transmit(dest, None, 1200), transmit(other, None, 1200)];
+        assert_eq!(gso_batch_len(&transmits, 1200, 8), 1);
+
+        // Without GSO support every transmit is sent on its own.
+        let transmits = vec![transmit(dest, None, 1200), transmit(dest, None, 1200)];
+        assert_eq!(gso_batch_len(&transmits, 1200, 1), 1);
+    }
+}